@@ -1,9 +1,13 @@
 use sc_service::ChainType;
-use solochain_template_runtime::{AccountId, Signature, WASM_BINARY};
-use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_consensus_grandpa::AuthorityId as GrandpaId;
-use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use serde::Deserialize;
+use solochain_template_runtime::{
+	genesis_config_presets::{
+		development_config_genesis, local_config_genesis, DEV_RUNTIME_PRESET,
+		LOCAL_TESTNET_RUNTIME_PRESET,
+	},
+	AccountId, Balance, EXISTENTIAL_DEPOSIT, WASM_BINARY,
+};
+use std::{collections::BTreeSet, path::Path};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -11,107 +15,191 @@ use sp_runtime::traits::{IdentifyAccount, Verify};
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec;
 
-/// Generate a crypto pair from seed.
-pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
-	TPublic::Pair::from_string(&format!("//{}", seed), None)
-		.expect("static values are valid; qed")
-		.public()
+/// An entry of an endowments file: an account and the balance it starts with.
+#[derive(Debug, Deserialize)]
+struct Endowment {
+	/// SS58 address of the account.
+	owner: AccountId,
+	/// Initial free balance of the account.
+	amount: Balance,
 }
 
-type AccountPublic = <Signature as Verify>::Signer;
+/// Read the accounts to pre-fund at genesis from a JSON file.
+///
+/// The file holds an array of `{ "owner": "<ss58 address>", "amount": <balance> }` objects. Every
+/// owner may appear only once and must be given at least the existential deposit.
+pub fn load_endowments(path: &Path) -> Result<Vec<(AccountId, Balance)>, String> {
+	let file = std::fs::File::open(path)
+		.map_err(|e| format!("Error opening endowments file `{}`: {}", path.display(), e))?;
+	let endowments: Vec<Endowment> = serde_json::from_reader(std::io::BufReader::new(file))
+		.map_err(|e| format!("Error parsing endowments file `{}`: {}", path.display(), e))?;
 
-/// Generate an account ID from seed.
-pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
-where
-	AccountPublic: From<<TPublic::Pair as Pair>::Public>,
-{
-	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
+	let endowments = endowments.into_iter().map(|e| (e.owner, e.amount)).collect::<Vec<_>>();
+	check_endowments(&endowments)?;
+	Ok(endowments)
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+/// Ensure every account is endowed only once and with at least the existential deposit.
+pub fn check_endowments(endowments: &[(AccountId, Balance)]) -> Result<(), String> {
+	let mut owners = BTreeSet::new();
+	for (owner, amount) in endowments {
+		if !owners.insert(owner) {
+			return Err(format!("Account {} is endowed more than once", owner))
+		}
+		if *amount < EXISTENTIAL_DEPOSIT {
+			return Err(format!(
+				"Account {} is endowed with {}, below the existential deposit of {}",
+				owner, amount, EXISTENTIAL_DEPOSIT
+			))
+		}
+	}
+	Ok(())
 }
 
-pub fn development_config() -> Result<ChainSpec, String> {
-	Ok(ChainSpec::builder(
+/// Ensure the sudo account is endowed, as it could not pay for its calls otherwise.
+pub fn check_root_endowed(
+	endowments: &[(AccountId, Balance)],
+	root: &AccountId,
+) -> Result<(), String> {
+	if !endowments.iter().any(|(owner, _)| owner == root) {
+		return Err(format!("The sudo account {} must be endowed to pay for its calls", root))
+	}
+	Ok(())
+}
+
+/// Replace the pre-funded accounts of a genesis config patch, which must include its sudo account.
+fn with_endowments(
+	mut patch: serde_json::Value,
+	endowments: Vec<(AccountId, Balance)>,
+) -> Result<serde_json::Value, String> {
+	let root: AccountId = serde_json::from_value(patch["sudo"]["key"].clone())
+		.map_err(|e| format!("Invalid sudo key in genesis config: {}", e))?;
+	check_root_endowed(&endowments, &root)?;
+
+	patch["balances"]["balances"] = serde_json::json!(endowments);
+	Ok(patch)
+}
+
+pub fn development_config(
+	endowments: Option<Vec<(AccountId, Balance)>>,
+) -> Result<ChainSpec, String> {
+	let builder = ChainSpec::builder(
 		WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
 		None,
 	)
 	.with_name("Development")
 	.with_id("dev")
-	.with_chain_type(ChainType::Development)
-	.with_genesis_config_patch(testnet_genesis(
-		// Initial PoA authorities
-		vec![authority_keys_from_seed("Alice")],
-		// Sudo account
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		// Pre-funded accounts
-		vec![
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			get_account_id_from_seed::<sr25519::Public>("Bob"),
-			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-		],
-		true,
-	))
+	.with_chain_type(ChainType::Development);
+
+	Ok(match endowments {
+		None => builder.with_genesis_config_preset_name(DEV_RUNTIME_PRESET),
+		Some(endowments) => builder
+			.with_genesis_config_patch(with_endowments(development_config_genesis(), endowments)?),
+	}
 	.build())
 }
 
-pub fn local_testnet_config() -> Result<ChainSpec, String> {
-	Ok(ChainSpec::builder(
+pub fn local_testnet_config(
+	endowments: Option<Vec<(AccountId, Balance)>>,
+) -> Result<ChainSpec, String> {
+	let builder = ChainSpec::builder(
 		WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
 		None,
 	)
 	.with_name("Local Testnet")
 	.with_id("local_testnet")
-	.with_chain_type(ChainType::Local)
-	.with_genesis_config_patch(testnet_genesis(
-		// Initial PoA authorities
-		vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
-		// Sudo account
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		// Pre-funded accounts
-		vec![
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			get_account_id_from_seed::<sr25519::Public>("Bob"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie"),
-			get_account_id_from_seed::<sr25519::Public>("Dave"),
-			get_account_id_from_seed::<sr25519::Public>("Eve"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-		],
-		true,
-	))
+	.with_chain_type(ChainType::Local);
+
+	Ok(match endowments {
+		None => builder.with_genesis_config_preset_name(LOCAL_TESTNET_RUNTIME_PRESET),
+		Some(endowments) =>
+			builder.with_genesis_config_patch(with_endowments(local_config_genesis(), endowments)?),
+	}
 	.build())
 }
 
-/// Configure initial storage state for FRAME modules.
-fn testnet_genesis(
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
-	endowed_accounts: Vec<AccountId>,
-	_enable_println: bool,
-) -> serde_json::Value {
-	serde_json::json!({
-		"balances": {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			"balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
-		},
-		"aura": {
-			"authorities": initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
-		},
-		"grandpa": {
-			"authorities": initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
-		},
-		"sudo": {
-			// Assign network admin rights.
-			"key": Some(root_key),
-		},
-	})
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::Sr25519Keyring;
+
+	// Write `content` to a file named after the test and load it as endowments.
+	fn load(test: &str, content: &str) -> Result<Vec<(AccountId, Balance)>, String> {
+		let path =
+			std::env::temp_dir().join(format!("endowments-{}-{}.json", test, std::process::id()));
+		std::fs::write(&path, content).unwrap();
+		let endowments = load_endowments(&path);
+		std::fs::remove_file(&path).unwrap();
+		endowments
+	}
+
+	fn entry(account: Sr25519Keyring, amount: Balance) -> String {
+		format!(r#"{{ "owner": "{}", "amount": {} }}"#, account.to_account_id(), amount)
+	}
+
+	#[test]
+	fn endowments_are_loaded() {
+		let json = format!(
+			"[{}, {}]",
+			entry(Sr25519Keyring::Alice, EXISTENTIAL_DEPOSIT),
+			entry(Sr25519Keyring::Bob, 2 * EXISTENTIAL_DEPOSIT)
+		);
+
+		assert_eq!(
+			load("loaded", &json).unwrap(),
+			vec![
+				(Sr25519Keyring::Alice.to_account_id(), EXISTENTIAL_DEPOSIT),
+				(Sr25519Keyring::Bob.to_account_id(), 2 * EXISTENTIAL_DEPOSIT)
+			]
+		);
+	}
+
+	#[test]
+	fn endowments_owners_are_unique() {
+		let json = format!(
+			"[{}, {}]",
+			entry(Sr25519Keyring::Alice, EXISTENTIAL_DEPOSIT),
+			entry(Sr25519Keyring::Alice, EXISTENTIAL_DEPOSIT)
+		);
+
+		let error = load("duplicate", &json).unwrap_err();
+		assert!(error.contains("endowed more than once"), "{}", error);
+	}
+
+	#[test]
+	fn endowments_cover_the_existential_deposit() {
+		let error =
+			check_endowments(&[(Sr25519Keyring::Alice.to_account_id(), EXISTENTIAL_DEPOSIT - 1)])
+				.unwrap_err();
+		assert!(error.contains("below the existential deposit"), "{}", error);
+	}
+
+	#[test]
+	fn endowments_owners_are_ss58_addresses() {
+		let json =
+			r#"[{ "owner": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQX", "amount": 1 }]"#;
+
+		let error = load("ss58", json).unwrap_err();
+		assert!(error.contains("Error parsing endowments file"), "{}", error);
+	}
+
+	#[test]
+	fn endowments_include_the_sudo_account() {
+		let error = with_endowments(
+			development_config_genesis(),
+			vec![(Sr25519Keyring::Bob.to_account_id(), EXISTENTIAL_DEPOSIT)],
+		)
+		.unwrap_err();
+		assert!(error.contains("must be endowed"), "{}", error);
+
+		let patch = with_endowments(
+			development_config_genesis(),
+			vec![(Sr25519Keyring::Alice.to_account_id(), EXISTENTIAL_DEPOSIT)],
+		)
+		.unwrap();
+		assert_eq!(
+			patch["balances"]["balances"],
+			serde_json::json!([(Sr25519Keyring::Alice.to_account_id(), EXISTENTIAL_DEPOSIT)])
+		);
+	}
 }
@@ -1,4 +1,5 @@
 use sc_cli::RunCmd;
+use std::path::PathBuf;
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +8,12 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// JSON file of `{ "owner": <ss58 address>, "amount": <balance> }` entries replacing the
+	/// pre-funded accounts of the built-in `dev` and `local` chain specs. The sudo account must be
+	/// among them. Rejected by subcommands that do not load a chain spec.
+	#[arg(long, value_name = "FILE", global = true)]
+	pub endowments: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -43,4 +50,7 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Interactively create the raw chain spec of a new network.
+	NewChain(crate::new_chain::NewChainCmd),
 }
@@ -36,9 +36,13 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		let endowments = self.endowments.as_deref().map(chain_spec::load_endowments).transpose()?;
+
 		Ok(match id {
-			"dev" => Box::new(chain_spec::development_config()?),
-			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
+			"dev" => Box::new(chain_spec::development_config(endowments)?),
+			"" | "local" => Box::new(chain_spec::local_testnet_config(endowments)?),
+			_ if endowments.is_some() =>
+				return Err("`--endowments` only applies to the `dev` and `local` chains".into()),
 			path =>
 				Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
 		})
@@ -49,6 +53,13 @@ impl SubstrateCli for Cli {
 pub fn run() -> sc_cli::Result<()> {
 	let cli = Cli::from_args();
 
+	// These subcommands never load a chain spec, so the endowments would be silently ignored.
+	if cli.endowments.is_some() &&
+		matches!(cli.subcommand, Some(Subcommand::Key(_)) | Some(Subcommand::NewChain(_)))
+	{
+		return Err("`--endowments` has no effect on the `key` and `new-chain` subcommands".into())
+	}
+
 	match &cli.subcommand {
 		Some(Subcommand::Key(cmd)) => cmd.run(&cli),
 		Some(Subcommand::BuildSpec(cmd)) => {
@@ -174,6 +185,7 @@ pub fn run() -> sc_cli::Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run::<Block>(&config))
 		},
+		Some(Subcommand::NewChain(cmd)) => cmd.run(),
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| async move {
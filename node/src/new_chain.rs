@@ -0,0 +1,299 @@
+//! The `new-chain` subcommand: an interactive wizard bootstrapping the chain spec of a new network.
+
+use crate::chain_spec::{self, ChainSpec};
+use sc_cli::Result;
+use sc_service::{ChainType, Properties};
+use solochain_template_runtime::{
+	genesis_config_presets::testnet_genesis, AccountId, Balance, SS58Prefix, MILLISECS_PER_BLOCK,
+	WASM_BINARY,
+};
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_consensus_grandpa::AuthorityId as GrandpaId;
+use sp_core::{crypto::Ss58Codec, ed25519, sr25519};
+use std::{
+	collections::BTreeSet,
+	io::{self, BufRead, Write},
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+/// The `new-chain` command used to create a ready-to-run raw chain spec.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct NewChainCmd {
+	/// Where to write the raw chain spec. The file must not exist yet.
+	#[arg(long, short, value_name = "FILE", default_value = "chain-spec.json")]
+	pub output: PathBuf,
+}
+
+impl NewChainCmd {
+	/// Ask for the parameters of the new chain on stdin and write its raw chain spec.
+	pub fn run(&self) -> Result<()> {
+		if self.output.exists() {
+			return Err(format!("Chain spec file `{}` already exists", self.output.display()).into())
+		}
+
+		let mut input = io::stdin().lock();
+		let name = prompt(&mut input, "Chain name", Some("My Chain"))?;
+		let id = prompt(&mut input, "Chain id", Some(&name.to_lowercase().replace(' ', "_")))?;
+		let token_symbol = prompt(&mut input, "Token symbol", Some("UNIT"))?;
+		let token_decimals: u8 =
+			parse(&prompt(&mut input, "Token decimals", Some("12"))?, "token decimals")?;
+		let block_time: u64 = parse(
+			&prompt(
+				&mut input,
+				"Block time in milliseconds",
+				Some(&MILLISECS_PER_BLOCK.to_string()),
+			)?,
+			"block time",
+		)?;
+		check_block_time(block_time)?;
+		let authorities = parse_authorities(&prompt(
+			&mut input,
+			"Authorities, as comma separated `<aura sr25519>:<grandpa ed25519>` SS58 pairs",
+			None,
+		)?)?;
+		let root = parse_account(&prompt(&mut input, "Sudo account", None)?)?;
+		let distribution = prompt(
+			&mut input,
+			"Initial distribution CSV file, with one `<owner>,<amount>` entry per line",
+			None,
+		)?;
+		let endowments = load_distribution(Path::new(&distribution))?;
+		chain_spec::check_root_endowed(&endowments, &root)?;
+
+		let mut properties = Properties::new();
+		properties.insert("tokenSymbol".into(), token_symbol.into());
+		properties.insert("tokenDecimals".into(), token_decimals.into());
+		properties.insert("ss58Format".into(), SS58Prefix::get().into());
+
+		let chain_spec = ChainSpec::builder(
+			WASM_BINARY.ok_or_else(|| "Wasm binary not available".to_string())?,
+			None,
+		)
+		.with_name(&name)
+		.with_id(&id)
+		.with_chain_type(ChainType::Live)
+		.with_properties(properties)
+		.with_genesis_config_patch(testnet_genesis(authorities, endowments, root))
+		.build();
+
+		// Never overwrite an existing chain spec, which may be the one of a live network, even if it
+		// was created while the questions were answered.
+		let mut output = std::fs::OpenOptions::new()
+			.write(true)
+			.create_new(true)
+			.open(&self.output)
+			.map_err(|e| {
+				format!("Error creating chain spec file `{}`: {}", self.output.display(), e)
+			})?;
+		output.write_all(chain_spec.as_json(true)?.as_bytes())?;
+		println!("Raw chain spec written to {}", self.output.display());
+
+		Ok(())
+	}
+}
+
+/// Print `question` and read the answer, falling back to `default` on an empty line.
+fn prompt(input: &mut impl BufRead, question: &str, default: Option<&str>) -> Result<String> {
+	match default {
+		Some(default) => print!("{} [{}]: ", question, default),
+		None => print!("{}: ", question),
+	}
+	io::stdout().flush()?;
+
+	let mut answer = String::new();
+	if input.read_line(&mut answer)? == 0 {
+		return Err("Unexpected end of input".into())
+	}
+
+	match (answer.trim(), default) {
+		("", Some(default)) => Ok(default.to_string()),
+		("", None) => Err(format!("{} is required", question).into()),
+		(answer, _) => Ok(answer.to_string()),
+	}
+}
+
+/// The block time is compiled into the runtime, so only its current value can be used.
+fn check_block_time(block_time: u64) -> Result<()> {
+	if block_time != MILLISECS_PER_BLOCK {
+		return Err(format!(
+			"This node produces blocks every {} ms. To use another block time, change \
+			`MILLISECS_PER_BLOCK` in the runtime and rebuild the node before running this command.",
+			MILLISECS_PER_BLOCK
+		)
+		.into())
+	}
+	Ok(())
+}
+
+/// Read the initial distribution from a CSV file of `<owner>,<amount>` lines.
+fn load_distribution(path: &Path) -> Result<Vec<(AccountId, Balance)>> {
+	let file = std::fs::File::open(path)
+		.map_err(|e| format!("Error opening distribution file `{}`: {}", path.display(), e))?;
+	parse_distribution(io::BufReader::new(file))
+}
+
+/// Parse an initial distribution made of `<owner>,<amount>` lines.
+///
+/// Empty lines, lines starting with `#` and an `owner,amount` header are skipped.
+fn parse_distribution(input: impl BufRead) -> Result<Vec<(AccountId, Balance)>> {
+	let mut endowments = Vec::new();
+	for (index, line) in input.lines().enumerate() {
+		let line = line?;
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') || (index == 0 && line.starts_with("owner")) {
+			continue
+		}
+
+		let (owner, amount) = line
+			.split_once(',')
+			.ok_or_else(|| format!("Line {}: expected `<owner>,<amount>`", index + 1))?;
+		endowments.push((parse_account(owner.trim())?, parse(amount.trim(), "amount")?));
+	}
+
+	chain_spec::check_endowments(&endowments)?;
+	Ok(endowments)
+}
+
+fn parse_account(address: &str) -> Result<AccountId> {
+	AccountId::from_ss58check(address)
+		.map_err(|e| format!("Invalid account `{}`: {:?}", address, e).into())
+}
+
+/// Parse comma separated `<aura>:<grandpa>` pairs, each key being used only once.
+fn parse_authorities(list: &str) -> Result<Vec<(AuraId, GrandpaId)>> {
+	let authorities = list.split(',').map(parse_authority).collect::<Result<Vec<_>>>()?;
+
+	let mut aura_keys = BTreeSet::new();
+	let mut grandpa_keys = BTreeSet::new();
+	for (aura, grandpa) in &authorities {
+		if !aura_keys.insert(aura) {
+			return Err(format!("Aura key {} is used more than once", aura).into())
+		}
+		if !grandpa_keys.insert(grandpa) {
+			return Err(format!("Grandpa key {} is used more than once", grandpa).into())
+		}
+	}
+	Ok(authorities)
+}
+
+fn parse_authority(pair: &str) -> Result<(AuraId, GrandpaId)> {
+	let (aura, grandpa) = pair
+		.trim()
+		.split_once(':')
+		.ok_or_else(|| format!("Invalid authority `{}`: expected `<aura>:<grandpa>`", pair))?;
+	let aura = sr25519::Public::from_ss58check(aura)
+		.map_err(|e| format!("Invalid aura key `{}`: {:?}", aura, e))?;
+	let grandpa = ed25519::Public::from_ss58check(grandpa)
+		.map_err(|e| format!("Invalid grandpa key `{}`: {:?}", grandpa, e))?;
+	Ok((aura.into(), grandpa.into()))
+}
+
+fn parse<T: FromStr>(value: &str, what: &str) -> Result<T> {
+	value.parse().map_err(|_| format!("Invalid {}: `{}`", what, value).into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use solochain_template_runtime::EXISTENTIAL_DEPOSIT;
+	use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+
+	fn address(account: Sr25519Keyring) -> String {
+		account.to_account_id().to_ss58check()
+	}
+
+	fn authority(aura: Sr25519Keyring, grandpa: Ed25519Keyring) -> String {
+		format!("{}:{}", aura.public().to_ss58check(), grandpa.public().to_ss58check())
+	}
+
+	#[test]
+	fn distribution_skips_header_comments_and_empty_lines() {
+		let csv = format!(
+			"owner,amount\n# genesis accounts\n\n{},1000\n  {} , 2000  \n",
+			address(Sr25519Keyring::Alice),
+			address(Sr25519Keyring::Bob)
+		);
+
+		assert_eq!(
+			parse_distribution(csv.as_bytes()).unwrap(),
+			vec![
+				(Sr25519Keyring::Alice.to_account_id(), 1000),
+				(Sr25519Keyring::Bob.to_account_id(), 2000)
+			]
+		);
+	}
+
+	#[test]
+	fn distribution_lines_need_a_comma() {
+		let csv = format!(
+			"{},1000\n{} 2000\n",
+			address(Sr25519Keyring::Alice),
+			address(Sr25519Keyring::Bob)
+		);
+
+		let error = parse_distribution(csv.as_bytes()).unwrap_err().to_string();
+		assert!(error.contains("Line 2"), "{}", error);
+	}
+
+	#[test]
+	fn distribution_amounts_cover_the_existential_deposit() {
+		let csv = format!("{},{}\n", address(Sr25519Keyring::Alice), EXISTENTIAL_DEPOSIT - 1);
+
+		let error = parse_distribution(csv.as_bytes()).unwrap_err().to_string();
+		assert!(error.contains("below the existential deposit"), "{}", error);
+	}
+
+	#[test]
+	fn authorities_are_aura_and_grandpa_pairs() {
+		let authorities = parse_authorities(&format!(
+			"{}, {}",
+			authority(Sr25519Keyring::Alice, Ed25519Keyring::Alice),
+			authority(Sr25519Keyring::Bob, Ed25519Keyring::Bob)
+		))
+		.unwrap();
+		assert_eq!(
+			authorities,
+			vec![
+				(Sr25519Keyring::Alice.public().into(), Ed25519Keyring::Alice.public().into()),
+				(Sr25519Keyring::Bob.public().into(), Ed25519Keyring::Bob.public().into())
+			]
+		);
+
+		let aura = Sr25519Keyring::Alice.public().to_ss58check();
+		assert!(parse_authority(&aura).is_err());
+		assert!(parse_authority(&format!("{}:", aura)).is_err());
+		assert!(parse_authority(&format!("{}:not-a-key", aura)).is_err());
+	}
+
+	#[test]
+	fn authorities_are_unique() {
+		let duplicate_aura = format!(
+			"{},{}",
+			authority(Sr25519Keyring::Alice, Ed25519Keyring::Alice),
+			authority(Sr25519Keyring::Alice, Ed25519Keyring::Bob)
+		);
+		assert!(parse_authorities(&duplicate_aura).is_err());
+
+		let duplicate_grandpa = format!(
+			"{},{}",
+			authority(Sr25519Keyring::Alice, Ed25519Keyring::Alice),
+			authority(Sr25519Keyring::Bob, Ed25519Keyring::Alice)
+		);
+		assert!(parse_authorities(&duplicate_grandpa).is_err());
+	}
+
+	#[test]
+	fn block_time_must_match_the_runtime() {
+		assert!(check_block_time(MILLISECS_PER_BLOCK).is_ok());
+		assert!(check_block_time(MILLISECS_PER_BLOCK + 1).is_err());
+	}
+
+	#[test]
+	fn values_are_parsed_or_named_in_errors() {
+		assert_eq!(parse::<u8>("12", "token decimals").unwrap(), 12);
+
+		let error = parse::<u8>("256", "token decimals").unwrap_err().to_string();
+		assert!(error.contains("Invalid token decimals: `256`"), "{}", error);
+	}
+}
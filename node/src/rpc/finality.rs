@@ -0,0 +1,411 @@
+//! RPC serving GRANDPA finality proofs in a compact, self-describing format.
+//!
+//! The finality proofs returned by the standard endpoints are SCALE-encoded blobs that only
+//! Substrate-aware tooling can make sense of. This module decodes them on the node and exposes
+//! the commit as plain JSON, with keys, signatures and headers as hex, so third-party verifiers
+//! only need ed25519, blake2 and a SCALE header decoder to check finality.
+//!
+//! A proof is only as good as the authority set it is checked against, and the node answering the
+//! request is not trusted. The verifier must therefore already know the authority set of the
+//! returned `set_id`, starting from the genesis one, and follow the `ScheduledChange` and
+//! `ForcedChange` GRANDPA digests of the headers it verifies to learn the next sets. The
+//! `authorities` of a response are only what the node reports, for the verifier to compare
+//! against the set it tracks.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{error::ErrorObject, ErrorObjectOwned},
+};
+use sc_consensus_grandpa::{
+	AuthoritySetChanges, FinalityProofError, FinalityProofProvider, SharedAuthoritySet,
+};
+use serde::{Deserialize, Serialize};
+use solochain_template_runtime::{opaque::Block, BlockNumber, Hash};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_grandpa::{AuthorityList, GrandpaApi, SetId};
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+type Header = <Block as BlockT>::Header;
+
+/// Version of the [`CompactFinalityProof`] layout, bumped whenever it changes.
+pub const FINALITY_PROOF_FORMAT_VERSION: u32 = 1;
+
+/// Error code returned when the node fails to build a finality proof.
+const FINALITY_PROOF_ERROR: i32 = 1;
+
+/// A member of a GRANDPA authority set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authority {
+	/// Raw ed25519 public key of the authority.
+	pub id: Bytes,
+	/// Voting weight of the authority.
+	pub weight: u64,
+}
+
+/// A precommit for a block, signed by one GRANDPA authority.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedPrecommit {
+	/// Hash of the block the authority voted for.
+	pub target_hash: Hash,
+	/// Number of the block the authority voted for.
+	pub target_number: BlockNumber,
+	/// Raw ed25519 public key of the authority.
+	pub authority: Bytes,
+	/// Raw ed25519 signature over the SCALE-encoded `(Message::Precommit(precommit), round,
+	/// set_id)` tuple.
+	pub signature: Bytes,
+}
+
+/// A GRANDPA justification, along with the authority set that the node reports as its author.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactJustification {
+	/// The GRANDPA round in which the block was finalized.
+	pub round: u64,
+	/// Id of the authority set that voted in `round`.
+	pub set_id: SetId,
+	/// Members of the authority set that voted in `round`, as read from the node's state. They are
+	/// not authenticated: verifiers must check the precommits against the set they track for
+	/// `set_id`, of which more than two thirds of the total weight finalize the target.
+	pub authorities: Vec<Authority>,
+	/// Hash of the finalized block.
+	pub target_hash: Hash,
+	/// Number of the finalized block.
+	pub target_number: BlockNumber,
+	/// Precommits from a supermajority of the authority set.
+	pub precommits: Vec<SignedPrecommit>,
+	/// SCALE-encoded headers linking every precommit target back to the finalized block.
+	pub votes_ancestries: Vec<Bytes>,
+}
+
+impl CompactJustification {
+	/// Describe a justification produced by the authority set `set_id`, made of `authorities`.
+	pub fn new(
+		justification: sp_consensus_grandpa::GrandpaJustification<Header>,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> Self {
+		let commit = justification.commit;
+		CompactJustification {
+			round: justification.round,
+			set_id,
+			authorities: authorities
+				.into_iter()
+				.map(|(id, weight)| Authority { id: id.encode().into(), weight })
+				.collect(),
+			target_hash: commit.target_hash,
+			target_number: commit.target_number,
+			precommits: commit
+				.precommits
+				.into_iter()
+				.map(|signed| SignedPrecommit {
+					target_hash: signed.precommit.target_hash,
+					target_number: signed.precommit.target_number,
+					authority: signed.id.encode().into(),
+					signature: signed.signature.encode().into(),
+				})
+				.collect(),
+			votes_ancestries: justification
+				.votes_ancestries
+				.iter()
+				.map(|header| header.encode().into())
+				.collect(),
+		}
+	}
+}
+
+/// Proof that a block is finalized, for a verifier tracking the GRANDPA authority sets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactFinalityProof {
+	/// Layout version, see [`FINALITY_PROOF_FORMAT_VERSION`].
+	pub version: u32,
+	/// Hash of the block whose finality is proven.
+	pub block_hash: Hash,
+	/// Justification of the block or of one of its descendants.
+	pub justification: CompactJustification,
+	/// SCALE-encoded headers after the block, up to and including the justification target,
+	/// chaining the block to it. Empty when the block is the justification target.
+	pub headers: Vec<Bytes>,
+}
+
+impl CompactFinalityProof {
+	/// Describe the proof that `block_hash` is finalized by `justification`, whose target is
+	/// chained to the block by `headers`.
+	pub fn new(
+		block_hash: Hash,
+		justification: CompactJustification,
+		headers: Vec<Header>,
+	) -> Self {
+		CompactFinalityProof {
+			version: FINALITY_PROOF_FORMAT_VERSION,
+			block_hash,
+			justification,
+			headers: headers.iter().map(|header| header.encode().into()).collect(),
+		}
+	}
+}
+
+/// Finality RPC methods.
+#[rpc(server, namespace = "grandpa")]
+pub trait FinalityApi {
+	/// Returns a proof that the given block is finalized.
+	///
+	/// Nodes only store justifications for blocks that change the authority set, for one block in
+	/// every justification period and for the latest finalized block. The proof therefore carries
+	/// the first such justification at or after the block, with the headers in between. `None`
+	/// is returned for blocks that are not finalized yet.
+	///
+	/// The authority set is not authenticated by the proof, see the [module documentation](self).
+	/// The reported authorities are read from the state of the parent of the justification target,
+	/// so nodes pruning state may fail to prove the finality of old blocks.
+	#[method(name = "finalityProof")]
+	fn finality_proof(&self, hash: Hash) -> RpcResult<Option<CompactFinalityProof>>;
+}
+
+/// Provides compact finality proofs for the blocks finalized by the client.
+pub struct Finality<C, B> {
+	client: Arc<C>,
+	shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
+	finality_proof_provider: Arc<FinalityProofProvider<B, Block>>,
+}
+
+impl<C, B> Finality<C, B> {
+	/// Create new `Finality` with the given reference to the client and GRANDPA components.
+	pub fn new(
+		client: Arc<C>,
+		shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
+		finality_proof_provider: Arc<FinalityProofProvider<B, Block>>,
+	) -> Self {
+		Self { client, shared_authority_set, finality_proof_provider }
+	}
+}
+
+impl<C, B> Finality<C, B>
+where
+	C: HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+	C::Api: GrandpaApi<Block>,
+{
+	/// Id and members of the authority set that finalized the given block.
+	fn authority_set(
+		&self,
+		target_hash: Hash,
+		target_number: BlockNumber,
+	) -> RpcResult<(SetId, AuthorityList)> {
+		let set_id = set_id_of(
+			&self.shared_authority_set.authority_set_changes(),
+			self.shared_authority_set.set_id(),
+			target_number,
+		)
+		.ok_or_else(|| finality_error("Unable to find authority set", "missing set changes"))?;
+
+		// A new set is enacted by the last block of the previous one, so the parent state still
+		// holds the authorities that voted on the target.
+		let parent_hash = self
+			.client
+			.header(target_hash)
+			.map_err(|e| finality_error("Unable to read header", e))?
+			.ok_or_else(|| finality_error("Unable to read header", target_hash))?
+			.parent_hash;
+		let authorities = self
+			.client
+			.runtime_api()
+			.grandpa_authorities(parent_hash)
+			.map_err(|e| finality_error("Unable to read authorities", e))?;
+
+		Ok((set_id, authorities))
+	}
+}
+
+impl<C, B> FinalityApiServer for Finality<C, B>
+where
+	C: HeaderBackend<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	C::Api: GrandpaApi<Block>,
+	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+{
+	fn finality_proof(&self, hash: Hash) -> RpcResult<Option<CompactFinalityProof>> {
+		let Some(number) = self
+			.client
+			.number(hash)
+			.map_err(|e| finality_error("Unable to read header", e))?
+		else {
+			return Ok(None)
+		};
+		// Only blocks of the finalized chain can be proven, not those of abandoned forks.
+		if number > self.client.info().finalized_number ||
+			self.client
+				.hash(number)
+				.map_err(|e| finality_error("Unable to read header", e))? !=
+				Some(hash)
+		{
+			return Ok(None)
+		}
+
+		let proof = match self.finality_proof_provider.prove_finality_proof(number, true) {
+			Ok(Some(proof)) => proof,
+			Ok(None) | Err(FinalityProofError::BlockNotYetFinalized) => return Ok(None),
+			Err(e) => return Err(finality_error("Unable to prove finality", e)),
+		};
+		let justification = sp_consensus_grandpa::GrandpaJustification::<Header>::decode(
+			&mut &proof.justification[..],
+		)
+		.map_err(|e| finality_error("Unable to decode justification", e))?;
+		let (set_id, authorities) = self
+			.authority_set(justification.commit.target_hash, justification.commit.target_number)?;
+
+		Ok(Some(CompactFinalityProof::new(
+			hash,
+			CompactJustification::new(justification, set_id, authorities),
+			proof.unknown_headers,
+		)))
+	}
+}
+
+/// Id of the authority set that finalized block `number`, given the past set changes recorded by
+/// the client and the id of the current set. `None` if the client lacks the changes to tell.
+fn set_id_of(
+	changes: &AuthoritySetChanges<BlockNumber>,
+	current_set_id: SetId,
+	number: BlockNumber,
+) -> Option<SetId> {
+	// The client records the last block of every past set, so the block belongs to the first set
+	// ending at or after it, or to the current one.
+	Some(
+		changes
+			.iter_from(number.saturating_sub(1))?
+			.next()
+			.map(|(set_id, _)| *set_id)
+			.unwrap_or(current_set_id),
+	)
+}
+
+fn finality_error(message: &str, error: impl std::fmt::Display) -> ErrorObjectOwned {
+	ErrorObject::owned(FINALITY_PROOF_ERROR, message, Some(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_consensus_grandpa::{AuthorityId, AuthoritySignature, Message, Precommit};
+	use sp_keyring::Ed25519Keyring;
+	use sp_runtime::{traits::Header as _, Digest};
+
+	fn header(number: BlockNumber, parent_hash: Hash) -> Header {
+		Header::new(number, Default::default(), Default::default(), parent_hash, Digest::default())
+	}
+
+	#[test]
+	fn justification_is_converted_to_raw_keys_and_signatures() {
+		let finalized = header(1, Hash::repeat_byte(1));
+		let voted = header(2, finalized.hash());
+		let precommit = Precommit::<Header> { target_hash: voted.hash(), target_number: 2 };
+		let signature = Ed25519Keyring::Alice.sign(&sp_consensus_grandpa::localized_payload(
+			3,
+			7,
+			&Message::<Header>::Precommit(precommit.clone()),
+		));
+		let justification = sp_consensus_grandpa::GrandpaJustification::<Header> {
+			round: 3,
+			commit: sp_consensus_grandpa::Commit::<Header> {
+				target_hash: finalized.hash(),
+				target_number: 1,
+				precommits: vec![finality_grandpa::SignedPrecommit {
+					precommit: precommit.clone(),
+					signature: signature.into(),
+					id: Ed25519Keyring::Alice.public().into(),
+				}],
+			},
+			votes_ancestries: vec![voted.clone()],
+		};
+
+		let compact = CompactJustification::new(
+			justification,
+			7,
+			vec![(Ed25519Keyring::Alice.public().into(), 1)],
+		);
+
+		assert_eq!((compact.round, compact.set_id), (3, 7));
+		assert_eq!((compact.target_hash, compact.target_number), (finalized.hash(), 1));
+		assert_eq!(compact.authorities.len(), 1);
+		assert_eq!(compact.authorities[0].id.len(), 32);
+		assert_eq!(compact.precommits.len(), 1);
+		assert_eq!(compact.precommits[0].authority.len(), 32);
+		assert_eq!(compact.precommits[0].signature.len(), 64);
+		assert_eq!(compact.precommits[0].authority, compact.authorities[0].id);
+		// The signature can be checked from the compact justification alone.
+		assert!(sp_consensus_grandpa::check_message_signature(
+			&Message::<Header>::Precommit(precommit),
+			&AuthorityId::decode(&mut &compact.precommits[0].authority[..]).unwrap(),
+			&AuthoritySignature::decode(&mut &compact.precommits[0].signature[..]).unwrap(),
+			compact.round,
+			compact.set_id,
+		));
+		let ancestries = compact
+			.votes_ancestries
+			.iter()
+			.map(|header| Header::decode(&mut &header[..]).unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(ancestries, vec![voted]);
+	}
+
+	#[test]
+	fn set_id_is_the_one_of_the_first_set_ending_at_or_after_the_block() {
+		// Set 0 ended with block 10 and set 1 with block 20, set 2 is the current one.
+		let changes = AuthoritySetChanges::from(vec![(0, 10), (1, 20)]);
+
+		assert_eq!(set_id_of(&changes, 2, 1), Some(0));
+		assert_eq!(set_id_of(&changes, 2, 10), Some(0));
+		assert_eq!(set_id_of(&changes, 2, 11), Some(1));
+		assert_eq!(set_id_of(&changes, 2, 20), Some(1));
+		assert_eq!(set_id_of(&changes, 2, 21), Some(2));
+		assert_eq!(set_id_of(&AuthoritySetChanges::from(vec![]), 0, 5), Some(0));
+
+		// Changes before set 3 were not recorded, e.g. after a warp sync.
+		let changes = AuthoritySetChanges::from(vec![(3, 10)]);
+		assert_eq!(set_id_of(&changes, 4, 5), None);
+		assert_eq!(set_id_of(&changes, 4, 11), Some(4));
+	}
+
+	#[test]
+	fn proof_chains_the_block_to_a_later_justification() {
+		let requested = header(1, Hash::repeat_byte(1));
+		let next = header(2, requested.hash());
+		let finalized = header(3, next.hash());
+		let justification = sp_consensus_grandpa::GrandpaJustification::<Header> {
+			round: 1,
+			commit: sp_consensus_grandpa::Commit::<Header> {
+				target_hash: finalized.hash(),
+				target_number: 3,
+				precommits: vec![],
+			},
+			votes_ancestries: vec![],
+		};
+
+		let proof = CompactFinalityProof::new(
+			requested.hash(),
+			CompactJustification::new(justification, 0, vec![]),
+			vec![next, finalized.clone()],
+		);
+
+		assert_eq!(proof.version, FINALITY_PROOF_FORMAT_VERSION);
+		assert_eq!(proof.block_hash, requested.hash());
+		let headers = proof
+			.headers
+			.iter()
+			.map(|header| Header::decode(&mut &header[..]).unwrap())
+			.collect::<Vec<_>>();
+		// Every header is the child of the previous one, from the requested block to the target.
+		assert_eq!(headers[0].parent_hash, proof.block_hash);
+		assert_eq!(headers[1].parent_hash, headers[0].hash());
+		assert_eq!(headers[1].hash(), proof.justification.target_hash);
+		assert_eq!(headers[1], finalized);
+	}
+}
@@ -211,10 +211,20 @@ pub fn new_full<
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let shared_authority_set = grandpa_link.shared_authority_set().clone();
+		let finality_proof_provider = sc_consensus_grandpa::FinalityProofProvider::new_for_service(
+			backend.clone(),
+			Some(shared_authority_set.clone()),
+		);
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				shared_authority_set: shared_authority_set.clone(),
+				finality_proof_provider: finality_proof_provider.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
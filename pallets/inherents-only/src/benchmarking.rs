@@ -0,0 +1,25 @@
+//! Benchmarking setup for pallet-inherents-only
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as InherentsOnly;
+use frame_benchmarking::v2::*;
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn enter_safe_mode() {
+		let until = frame_system::Pallet::<T>::block_number() + T::MaxSafeModeBlocks::get();
+		#[extrinsic_call]
+		enter_safe_mode(RawOrigin::Root, until);
+
+		assert_eq!(SafeModeUntil::<T>::get(), until);
+	}
+
+	impl_benchmark_test_suite!(InherentsOnly, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,124 @@
+//! # Inherents-Only Pallet
+//!
+//! Lets the root origin put the chain in safe mode for a bounded number of blocks. While safe mode
+//! is active, blocks only contain inherents: user transactions are halted while block production
+//! and finality carry on.
+//!
+//! Safe mode is enforced by the executive, through [`SafeMode`] reporting a multi-block migration
+//! as ongoing. It therefore has the same side effects as a real one: the `on_idle` and `on_poll`
+//! hooks of every pallet are skipped and `system.set_code` is refused. Since no extrinsic can be
+//! applied to leave it early either, [`Config::MaxSafeModeBlocks`] bounds how long the chain can
+//! stay in safe mode.
+//!
+//! Unlike the `pallet-safe-mode` of the Polkadot SDK, which filters calls through the runtime's
+//! `BaseCallFilter`, nothing but inherents reaches the block while safe mode is active.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+use core::marker::PhantomData;
+use frame_support::{migrations::MultiStepMigrator, weights::Weight};
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		pallet_prelude::*,
+		sp_runtime::traits::{One, Saturating},
+	};
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// How many blocks ahead of the current one safe mode can be entered for at most.
+		#[pallet::constant]
+		type MaxSafeModeBlocks: Get<BlockNumberFor<Self>>;
+
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Blocks with a number below this value are built in safe mode.
+	#[pallet::storage]
+	pub type SafeModeUntil<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Blocks after the current one and below `until` will only contain inherents.
+		SafeModeEntered { until: BlockNumberFor<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// `until` is more than `MaxSafeModeBlocks` ahead of the current block.
+		TooLong,
+		/// `until` is not after the next block, so no block would be built in safe mode.
+		TooShort,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Halt user transactions from the next block up to, but excluding, block `until`.
+		///
+		/// `until` must be after the next block and at most `MaxSafeModeBlocks` ahead of the
+		/// current one. Safe mode lifts itself once block `until` is reached and cannot be exited
+		/// before.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::enter_safe_mode())]
+		pub fn enter_safe_mode(origin: OriginFor<T>, until: BlockNumberFor<T>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(until > now.saturating_add(One::one()), Error::<T>::TooShort);
+			ensure!(until <= now.saturating_add(T::MaxSafeModeBlocks::get()), Error::<T>::TooLong);
+
+			SafeModeUntil::<T>::put(until);
+			Self::deposit_event(Event::SafeModeEntered { until });
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether the current block is built in safe mode.
+		pub fn is_active() -> bool {
+			frame_system::Pallet::<T>::block_number() < SafeModeUntil::<T>::get()
+		}
+	}
+}
+
+/// `frame_system::Config::MultiBlockMigrator` making the executive only include inherents while
+/// safe mode is active.
+///
+/// `M` is the runtime's actual multi-block migrator, if any. A migration is reported as ongoing
+/// while either safe mode is active or `M` has one ongoing, and `M` is stepped as usual.
+pub struct SafeMode<T, M = ()>(PhantomData<(T, M)>);
+
+impl<T: Config, M: MultiStepMigrator> MultiStepMigrator for SafeMode<T, M> {
+	fn ongoing() -> bool {
+		Pallet::<T>::is_active() || M::ongoing()
+	}
+
+	fn step() -> Weight {
+		M::step()
+	}
+}
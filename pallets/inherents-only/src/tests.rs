@@ -0,0 +1,72 @@
+use crate::{mock::*, Error, Event, SafeModeUntil};
+use frame_support::{assert_noop, assert_ok, migrations::MultiStepMigrator};
+use sp_runtime::DispatchError;
+
+type Migrator = crate::SafeMode<Test>;
+
+#[test]
+fn only_root_can_enter_safe_mode() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			InherentsOnly::enter_safe_mode(RuntimeOrigin::signed(1), 5),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn safe_mode_is_bounded() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			InherentsOnly::enter_safe_mode(RuntimeOrigin::root(), 12),
+			Error::<Test>::TooLong
+		);
+		assert_noop!(
+			InherentsOnly::enter_safe_mode(RuntimeOrigin::root(), u64::MAX),
+			Error::<Test>::TooLong
+		);
+
+		assert_ok!(InherentsOnly::enter_safe_mode(RuntimeOrigin::root(), 11));
+		assert_eq!(SafeModeUntil::<Test>::get(), 11);
+		System::assert_last_event(Event::SafeModeEntered { until: 11 }.into());
+	});
+}
+
+#[test]
+fn safe_mode_covers_at_least_one_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		// Safe mode starts with the next block, so it would never be active.
+		assert_noop!(
+			InherentsOnly::enter_safe_mode(RuntimeOrigin::root(), 1),
+			Error::<Test>::TooShort
+		);
+		assert_noop!(
+			InherentsOnly::enter_safe_mode(RuntimeOrigin::root(), 2),
+			Error::<Test>::TooShort
+		);
+
+		assert_ok!(InherentsOnly::enter_safe_mode(RuntimeOrigin::root(), 3));
+		System::set_block_number(2);
+		assert!(Migrator::ongoing());
+	});
+}
+
+#[test]
+fn safe_mode_lifts_itself() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert!(!Migrator::ongoing());
+		assert_ok!(InherentsOnly::enter_safe_mode(RuntimeOrigin::root(), 4));
+
+		for block in 2..4 {
+			System::set_block_number(block);
+			assert!(Migrator::ongoing());
+		}
+
+		System::set_block_number(4);
+		assert!(!Migrator::ongoing());
+	});
+}
@@ -0,0 +1,48 @@
+//! Weights for pallet_inherents_only.
+//!
+//! Regenerate them for the target hardware with the `benchmark pallet` subcommand of the node:
+//!
+//! ```sh
+//! ./target/release/solochain-template-node benchmark pallet --chain dev \
+//!   --pallet pallet_inherents_only --extrinsic '*' --steps 50 --repeat 20 \
+//!   --wasm-execution compiled --output pallets/inherents-only/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_inherents_only.
+pub trait WeightInfo {
+	fn enter_safe_mode() -> Weight;
+}
+
+/// Weights for pallet_inherents_only using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: InherentsOnly SafeModeUntil (r:0 w:1)
+	/// Proof: InherentsOnly SafeModeUntil (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn enter_safe_mode() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		Weight::from_parts(9_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: InherentsOnly SafeModeUntil (r:0 w:1)
+	/// Proof: InherentsOnly SafeModeUntil (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn enter_safe_mode() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		Weight::from_parts(9_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -0,0 +1,103 @@
+//! Named genesis configurations served through the `GenesisBuilder` runtime API.
+//!
+//! These are picked up by `chain-spec-builder` and by the node's built-in chain specs, so the
+//! genesis state of the development and local testnet chains is defined in one place.
+
+use crate::{AccountId, Balance};
+use alloc::{vec, vec::Vec};
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_consensus_grandpa::AuthorityId as GrandpaId;
+use sp_genesis_builder::PresetId;
+use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+
+/// Name of the single-authority development preset.
+pub const DEV_RUNTIME_PRESET: &str = "development";
+
+/// Name of the two-authority local testnet preset.
+pub const LOCAL_TESTNET_RUNTIME_PRESET: &str = "local_testnet";
+
+/// Balance given to each pre-funded account of the built-in presets.
+pub const ENDOWMENT: Balance = 1 << 60;
+
+/// Returns a genesis config patch populated with the given parameters.
+///
+/// Every endowed account carries its own balance, so callers pick both the keys and the amounts
+/// of the initial distribution.
+pub fn testnet_genesis(
+	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	endowed_accounts: Vec<(AccountId, Balance)>,
+	root: AccountId,
+) -> serde_json::Value {
+	serde_json::json!({
+		"balances": {
+			"balances": endowed_accounts,
+		},
+		"aura": {
+			"authorities": initial_authorities.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
+		},
+		"grandpa": {
+			"authorities": initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
+		},
+		"sudo": {
+			// Assign network admin rights.
+			"key": Some(root),
+		},
+	})
+}
+
+/// Return the development genesis config.
+pub fn development_config_genesis() -> serde_json::Value {
+	testnet_genesis(
+		// Initial PoA authorities
+		vec![(Sr25519Keyring::Alice.public().into(), Ed25519Keyring::Alice.public().into())],
+		// Pre-funded accounts
+		vec![
+			Sr25519Keyring::Alice.to_account_id(),
+			Sr25519Keyring::Bob.to_account_id(),
+			Sr25519Keyring::AliceStash.to_account_id(),
+			Sr25519Keyring::BobStash.to_account_id(),
+		]
+		.into_iter()
+		.map(|k| (k, ENDOWMENT))
+		.collect(),
+		// Sudo account
+		Sr25519Keyring::Alice.to_account_id(),
+	)
+}
+
+/// Return the local testnet genesis config.
+pub fn local_config_genesis() -> serde_json::Value {
+	testnet_genesis(
+		// Initial PoA authorities
+		vec![
+			(Sr25519Keyring::Alice.public().into(), Ed25519Keyring::Alice.public().into()),
+			(Sr25519Keyring::Bob.public().into(), Ed25519Keyring::Bob.public().into()),
+		],
+		// Pre-funded accounts
+		Sr25519Keyring::iter()
+			.filter(|k| k != &Sr25519Keyring::One && k != &Sr25519Keyring::Two)
+			.map(|k| (k.to_account_id(), ENDOWMENT))
+			.collect(),
+		// Sudo account
+		Sr25519Keyring::Alice.to_account_id(),
+	)
+}
+
+/// Provides the JSON representation of predefined genesis config for given `id`.
+pub fn get_preset(id: &PresetId) -> Option<Vec<u8>> {
+	let patch = match id.try_into() {
+		Ok(DEV_RUNTIME_PRESET) => development_config_genesis(),
+		Ok(LOCAL_TESTNET_RUNTIME_PRESET) => local_config_genesis(),
+		_ => return None,
+	};
+	Some(
+		serde_json::to_string(&patch)
+			.expect("serialization to json is expected to work. qed.")
+			.into_bytes(),
+	)
+}
+
+/// List of supported presets.
+pub fn preset_names() -> Vec<PresetId> {
+	vec![PresetId::from(DEV_RUNTIME_PRESET), PresetId::from(LOCAL_TESTNET_RUNTIME_PRESET)]
+}
@@ -0,0 +1,113 @@
+use crate::*;
+use codec::Encode;
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::{
+	generic::{Digest, DigestItem, Era},
+	traits::Header as _,
+};
+
+// Build genesis storage with Alice as the only authority and funded account, with blocks below
+// `safe_mode_until` built in safe mode. The storage is written directly, as `enter_safe_mode` only
+// accepts values after the next block.
+fn new_test_ext(safe_mode_until: BlockNumber) -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = RuntimeGenesisConfig {
+		aura: AuraConfig { authorities: vec![Sr25519Keyring::Alice.public().into()] },
+		balances: BalancesConfig {
+			balances: vec![(
+				Sr25519Keyring::Alice.to_account_id(),
+				genesis_config_presets::ENDOWMENT,
+			)],
+		},
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap()
+	.into();
+	ext.execute_with(|| {
+		pallet_inherents_only::SafeModeUntil::<Runtime>::put(safe_mode_until);
+	});
+	ext
+}
+
+// A `system.remark` signed by Alice.
+fn signed_remark() -> UncheckedExtrinsic {
+	let call = RuntimeCall::System(SystemCall::remark { remark: vec![] });
+	let extra: SignedExtra = (
+		frame_system::CheckNonZeroSender::<Runtime>::new(),
+		frame_system::CheckSpecVersion::<Runtime>::new(),
+		frame_system::CheckTxVersion::<Runtime>::new(),
+		frame_system::CheckGenesis::<Runtime>::new(),
+		frame_system::CheckEra::<Runtime>::from(Era::Immortal),
+		frame_system::CheckNonce::<Runtime>::from(0),
+		frame_system::CheckWeight::<Runtime>::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+	);
+	let payload = SignedPayload::new(call.clone(), extra.clone()).unwrap();
+	let signature = payload.using_encoded(|payload| Sr25519Keyring::Alice.sign(payload));
+
+	UncheckedExtrinsic::new_signed(
+		call,
+		Sr25519Keyring::Alice.to_account_id().into(),
+		signature.into(),
+		extra,
+	)
+}
+
+// Author block #1 with the timestamp inherent and a signed remark, as the block builder would.
+fn author_block() -> Block {
+	let slot = Slot::from(1);
+	let digest = Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())] };
+	let header =
+		Header::new(1, Default::default(), Default::default(), System::block_hash(0), digest);
+	Executive::initialize_block(&header);
+
+	let extrinsics = vec![
+		UncheckedExtrinsic::new_unsigned(RuntimeCall::Timestamp(TimestampCall::set {
+			now: SLOT_DURATION,
+		})),
+		signed_remark(),
+	];
+	for extrinsic in &extrinsics {
+		assert_eq!(Executive::apply_extrinsic(extrinsic.clone()), Ok(Ok(())));
+	}
+
+	Block { header: Executive::finalize_block(), extrinsics }
+}
+
+#[test]
+#[should_panic(expected = "Only inherents are allowed in this block")]
+fn blocks_with_transactions_are_rejected_in_safe_mode() {
+	let block = new_test_ext(2).execute_with(author_block);
+	new_test_ext(2).execute_with(|| Executive::execute_block(block));
+}
+
+#[test]
+fn blocks_with_transactions_are_accepted_once_safe_mode_ends() {
+	let block = new_test_ext(1).execute_with(author_block);
+	new_test_ext(1).execute_with(|| Executive::execute_block(block));
+}
+
+// Apply a genesis config patch onto `base`, as the chain spec builder does with presets.
+fn merge(base: &mut serde_json::Value, patch: serde_json::Value) {
+	match (base, patch) {
+		(serde_json::Value::Object(base), serde_json::Value::Object(patch)) =>
+			for (key, value) in patch {
+				merge(base.entry(key).or_insert(serde_json::Value::Null), value);
+			},
+		(base, patch) => *base = patch,
+	}
+}
+
+#[test]
+fn genesis_presets_are_valid() {
+	for name in genesis_config_presets::preset_names() {
+		let preset = genesis_config_presets::get_preset(&name)
+			.unwrap_or_else(|| panic!("preset {:?} is listed but not provided", name));
+
+		let mut config = serde_json::to_value(RuntimeGenesisConfig::default()).unwrap();
+		merge(&mut config, serde_json::from_slice(&preset).unwrap());
+		serde_json::from_value::<RuntimeGenesisConfig>(config)
+			.unwrap_or_else(|e| panic!("preset {:?} is invalid: {}", name, e));
+	}
+}
@@ -0,0 +1,511 @@
+//! Transaction censorship monitor.
+//!
+//! Submits canary transactions through each of the given nodes in turn and waits for them to be
+//! included in a block. For every node it reports how long inclusion took and how many canaries
+//! never made it. For every Aura authority it reports how many canaries it included and how many
+//! blocks it authored while a canary was pending without including it.
+//!
+//! Blocks count as skipped from the first one authored after the canary entered the transaction
+//! pool of the node it was submitted through.
+//!
+//! Canaries are mortal and signed with nonces tracked from chain state. When one goes missing, the
+//! monitor waits until it is either included or expired before submitting the next, so a canary
+//! stuck in some transaction pool cannot hold back the following ones.
+//!
+//! A failing node does not stop the monitoring of the others: the canary of a round is counted as
+//! missed when an RPC request fails, and the round as unreachable when the node cannot be
+//! connected to.
+//!
+//! A canary can legitimately miss a block or two while it is still being gossiped, so a single
+//! skip means little. An authority that keeps skipping pending canaries while others include them,
+//! or a node whose canaries never get in, is worth a closer look.
+
+use clap::Parser;
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use jsonrpsee::{
+	core::client::{ClientT, Error as ClientError, Subscription, SubscriptionClientT},
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use runtime::{
+	AccountId, BlockNumber, Hash, Header, Nonce, Runtime, RuntimeCall, SignedExtra, SignedPayload,
+	SystemCall, UncheckedExtrinsic,
+};
+use solochain_template_runtime as runtime;
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_core::{crypto::Ss58Codec, sr25519, Bytes, Pair};
+use sp_runtime::generic::Era;
+use std::{
+	collections::BTreeMap,
+	time::{Duration, Instant},
+};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Number of blocks a canary stays valid for.
+const CANARY_LIFETIME: u64 = 32;
+
+/// Submit canary transactions through several nodes and report who includes them.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+	/// WebSocket RPC endpoint of a node to submit canaries through. Repeat for every node.
+	#[arg(long = "node", value_name = "URL", required = true)]
+	nodes: Vec<String>,
+
+	/// Secret URI of the account paying for the canaries.
+	#[arg(long, value_name = "SURI", default_value = "//Alice")]
+	signer: String,
+
+	/// Number of canaries to submit through each node.
+	#[arg(long, default_value_t = 10)]
+	rounds: u32,
+
+	/// Seconds to wait for a canary to be included before counting it as missed.
+	#[arg(long, value_name = "SECONDS", default_value_t = 60)]
+	timeout: u64,
+}
+
+/// Canary statistics for one node.
+#[derive(Debug, Default)]
+struct NodeStats {
+	/// Time to inclusion of every included canary.
+	included: Vec<Duration>,
+	/// Canaries that were rejected, dropped, not included before the timeout or that could not be
+	/// sent because of an RPC error.
+	missed: u32,
+	/// Rounds skipped because the node could not be reached.
+	unreachable: u32,
+}
+
+/// Canary statistics for one block author.
+#[derive(Debug, Default)]
+struct AuthorStats {
+	/// Canaries included in blocks authored by this authority.
+	included: u32,
+	/// Blocks authored by this authority while a canary was pending, without including it.
+	skipped: u32,
+}
+
+/// A canary transaction to submit.
+struct Canary {
+	/// Content of the remark, identifying the canary.
+	remark: Vec<u8>,
+	/// Nonce the canary is signed with.
+	nonce: Nonce,
+	/// Period during which the canary is valid.
+	era: Era,
+	/// Number of the first block of `era`.
+	birth: BlockNumber,
+}
+
+/// What carries over from one canary to the next.
+#[derive(Default)]
+struct Sender {
+	/// Next nonce of the signer, `None` when it must be read from chain state.
+	nonce: Option<Nonce>,
+	/// Canary that may still be in some transaction pool, to wait out before signing the next.
+	pending: Option<Canary>,
+}
+
+/// Where a canary ended up.
+enum Outcome {
+	/// Included in the given block after the given time.
+	Included {
+		block_hash: Hash,
+		elapsed: Duration,
+		/// First block authored after the canary entered the pool, if it was seen doing so.
+		pending_from: Option<BlockNumber>,
+	},
+	/// Refused by the node it was submitted through, so it never reached any pool.
+	Rejected(String),
+	/// Dropped, invalidated or still pending when the timeout expired.
+	Missed(String),
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+	let args = Args::parse();
+	let signer = sr25519::Pair::from_string(&args.signer, None)
+		.map_err(|e| format!("Invalid signer `{}`: {:?}", args.signer, e))?;
+	let timeout = Duration::from_secs(args.timeout);
+
+	let mut clients = Vec::with_capacity(args.nodes.len());
+	for url in &args.nodes {
+		let mut client = None;
+		connected(&mut client, url).await;
+		clients.push(client);
+	}
+	let genesis_hash = genesis_hash(&clients).await?;
+
+	let mut sender = Sender::default();
+	let mut node_stats: Vec<NodeStats> = args.nodes.iter().map(|_| Default::default()).collect();
+	let mut author_stats = BTreeMap::<sr25519::Public, AuthorStats>::new();
+
+	for round in 0..args.rounds {
+		for (index, url) in args.nodes.iter().enumerate() {
+			// A misbehaving node must not stop the monitoring of the others, so its failures are
+			// only counted against it.
+			let Some(client) = connected(&mut clients[index], url).await else {
+				node_stats[index].unreachable += 1;
+				continue
+			};
+			if sender.pending.is_some() {
+				println!("{}: waiting for the last canary to be included or expire", url);
+			}
+
+			let remark = format!("canary {} via {}", round, url).into_bytes();
+			match send_canary(client, &signer, genesis_hash, &mut sender, remark, timeout).await {
+				Ok(Outcome::Included { block_hash, elapsed, pending_from }) => {
+					println!("{}: canary {} included after {:.1?}", url, round, elapsed);
+					node_stats[index].included.push(elapsed);
+					if let Err(e) =
+						attribute(client, block_hash, pending_from, &mut author_stats).await
+					{
+						println!("{}: unable to find who skipped canary {}: {}", url, round, e);
+					}
+				},
+				Ok(Outcome::Rejected(reason)) => {
+					println!("{}: canary {} rejected: {}", url, round, reason);
+					node_stats[index].missed += 1;
+				},
+				Ok(Outcome::Missed(reason)) => {
+					println!("{}: canary {} missed: {}", url, round, reason);
+					node_stats[index].missed += 1;
+				},
+				Err(e) => {
+					println!("{}: canary {} failed: {}", url, round, e);
+					node_stats[index].missed += 1;
+				},
+			}
+		}
+	}
+
+	println!();
+	println!("Time to inclusion per node:");
+	for (url, stats) in args.nodes.iter().zip(&node_stats) {
+		let included = &stats.included;
+		let unreachable = match stats.unreachable {
+			0 => String::new(),
+			rounds => format!(", unreachable in {} rounds", rounds),
+		};
+		if included.is_empty() {
+			println!("  {}: none of {} canaries included{}", url, stats.missed, unreachable);
+			continue
+		}
+		let total: Duration = included.iter().sum();
+		println!(
+			"  {}: {} included, {} missed, min {:.1?}, avg {:.1?}, max {:.1?}{}",
+			url,
+			included.len(),
+			stats.missed,
+			included.iter().min().expect("not empty; qed"),
+			total / included.len() as u32,
+			included.iter().max().expect("not empty; qed"),
+			unreachable,
+		);
+	}
+
+	println!();
+	println!("Canaries per block author:");
+	for (author, stats) in &author_stats {
+		println!(
+			"  {}: {} included, {} blocks authored while one was pending",
+			author.to_ss58check(),
+			stats.included,
+			stats.skipped
+		);
+	}
+
+	Ok(())
+}
+
+/// The client of a node, connecting to it again if the connection was lost. `None` if the node
+/// cannot be reached.
+async fn connected<'a>(client: &'a mut Option<WsClient>, url: &str) -> Option<&'a WsClient> {
+	if !client.as_ref().is_some_and(|client| client.is_connected()) {
+		*client = match WsClientBuilder::default().build(url).await {
+			Ok(client) => Some(client),
+			Err(e) => {
+				println!("{}: unreachable: {}", url, e);
+				None
+			},
+		};
+	}
+	client.as_ref()
+}
+
+/// Hash of the genesis block, according to the first node answering.
+async fn genesis_hash(clients: &[Option<WsClient>]) -> Result<Hash> {
+	for client in clients.iter().flatten() {
+		if let Ok(hash) = block_hash(client, 0).await {
+			return Ok(hash)
+		}
+	}
+	Err("None of the nodes could be reached".into())
+}
+
+/// Wait out the last canary if needed, then sign a new one, submit it through `client` and wait
+/// until it is included in a block.
+async fn send_canary(
+	client: &WsClient,
+	signer: &sr25519::Pair,
+	genesis_hash: Hash,
+	sender: &mut Sender,
+	remark: Vec<u8>,
+	timeout: Duration,
+) -> Result<Outcome> {
+	let account = AccountId::from(signer.public());
+	if let Some(pending) = &sender.pending {
+		sender.nonce = Some(wait_out(client, &account, pending).await?);
+		sender.pending = None;
+	}
+	let nonce = match sender.nonce {
+		Some(nonce) => nonce,
+		None => account_nonce(client, &account).await?,
+	};
+
+	let best: Header = client.request("chain_getHeader", rpc_params![]).await?;
+	let era = Era::mortal(CANARY_LIFETIME, best.number.into());
+	let canary = Canary { remark, nonce, era, birth: era.birth(best.number.into()) as BlockNumber };
+	let context = signing_context(client, genesis_hash, canary.birth).await?;
+	let extrinsic = sign_remark(signer, &canary, &context);
+
+	// Whatever happens from now on, the canary may reach a pool until it is known not to have.
+	sender.pending = Some(canary);
+	let outcome = submit(client, extrinsic, timeout).await?;
+	match outcome {
+		Outcome::Included { .. } => {
+			sender.pending = None;
+			sender.nonce = Some(nonce + 1);
+		},
+		Outcome::Rejected(_) => {
+			sender.pending = None;
+			sender.nonce = None;
+		},
+		Outcome::Missed(_) => sender.nonce = None,
+	}
+	Ok(outcome)
+}
+
+/// Submit a signed canary through `client` and wait until it is included in a block.
+async fn submit(
+	client: &WsClient,
+	extrinsic: UncheckedExtrinsic,
+	timeout: Duration,
+) -> Result<Outcome> {
+	let started = Instant::now();
+	let mut status = match client
+		.subscribe::<serde_json::Value, _>(
+			"author_submitAndWatchExtrinsic",
+			rpc_params![Bytes(extrinsic.encode())],
+			"author_unwatchExtrinsic",
+		)
+		.await
+	{
+		Ok(status) => status,
+		Err(ClientError::Call(e)) => return Ok(Outcome::Rejected(e.to_string())),
+		Err(e) => return Err(e.into()),
+	};
+
+	match tokio::time::timeout(timeout, watch(client, &mut status, started)).await {
+		Ok(outcome) => outcome,
+		Err(_) => Ok(Outcome::Missed(format!("not included within {:?}", timeout))),
+	}
+}
+
+/// Follow the status updates of a submitted canary until it is included or given up on.
+async fn watch(
+	client: &WsClient,
+	status: &mut Subscription<serde_json::Value>,
+	started: Instant,
+) -> Result<Outcome> {
+	let mut pending_from = None;
+	while let Some(update) = StreamExt::next(status).await {
+		let update = update?;
+		if pending_from.is_none() && (update == "ready" || update.get("broadcast").is_some()) {
+			// Blocks authored before the canary entered the pool could not have included it.
+			let best: Header = client.request("chain_getHeader", rpc_params![]).await?;
+			pending_from = Some(best.number + 1);
+		}
+		if let Some(block_hash) = update.get("inBlock") {
+			return Ok(Outcome::Included {
+				block_hash: serde_json::from_value(block_hash.clone())?,
+				elapsed: started.elapsed(),
+				pending_from,
+			})
+		}
+		if update == "dropped" || update == "invalid" || update.get("usurped").is_some() {
+			return Ok(Outcome::Missed(update.to_string()))
+		}
+	}
+	Ok(Outcome::Missed("status subscription closed".into()))
+}
+
+/// Credit a canary to the author of the block including it, and count the blocks authored while
+/// it was pending, from `pending_from` on, as skipped by their authors.
+async fn attribute(
+	client: &WsClient,
+	block_hash: Hash,
+	pending_from: Option<BlockNumber>,
+	author_stats: &mut BTreeMap<sr25519::Public, AuthorStats>,
+) -> Result<()> {
+	let included_in = header(client, block_hash).await?;
+	// Look all the authors up first, so that a failure does not leave the statistics half updated.
+	let author = block_author(client, &included_in).await?;
+	let mut skipping = Vec::new();
+	for number in pending_from.unwrap_or(included_in.number)..included_in.number {
+		skipping.push(block_author(client, &header_at(client, number).await?).await?);
+	}
+
+	if let Some(author) = author {
+		author_stats.entry(author).or_default().included += 1;
+	}
+	for author in skipping.into_iter().flatten() {
+		author_stats.entry(author).or_default().skipped += 1;
+	}
+	Ok(())
+}
+
+/// Wait until a missed canary can no longer be included, because it either was or expired, and
+/// return the next nonce of `account`.
+async fn wait_out(client: &WsClient, account: &AccountId, canary: &Canary) -> Result<Nonce> {
+	let death = canary.era.death(canary.birth.into());
+	loop {
+		let best: Header = client.request("chain_getHeader", rpc_params![]).await?;
+		let nonce = account_nonce(client, account).await?;
+		if nonce > canary.nonce || u64::from(best.number) >= death {
+			return Ok(nonce)
+		}
+		tokio::time::sleep(Duration::from_millis(runtime::MILLISECS_PER_BLOCK)).await;
+	}
+}
+
+/// The next nonce of `account` according to the state of the best block, ignoring pending
+/// transactions.
+async fn account_nonce(client: &WsClient, account: &AccountId) -> Result<Nonce> {
+	let encoded: Bytes = client
+		.request(
+			"state_call",
+			rpc_params!["AccountNonceApi_account_nonce", Bytes(account.encode())],
+		)
+		.await?;
+	Ok(Nonce::decode(&mut &encoded[..])?)
+}
+
+/// What a transaction signature commits to, besides the call and the signed extensions.
+struct SigningContext {
+	genesis_hash: Hash,
+	/// Hash of the block the mortality period of the transaction starts at.
+	birth_hash: Hash,
+	spec_version: u32,
+	transaction_version: u32,
+}
+
+/// The signing context of a transaction sent through `client`, whose mortality period starts at
+/// block `birth`.
+async fn signing_context(
+	client: &WsClient,
+	genesis_hash: Hash,
+	birth: BlockNumber,
+) -> Result<SigningContext> {
+	let version: serde_json::Value =
+		client.request("state_getRuntimeVersion", rpc_params![]).await?;
+	Ok(SigningContext {
+		genesis_hash,
+		birth_hash: block_hash(client, birth).await?,
+		spec_version: version["specVersion"].as_u64().ok_or("Missing spec version")? as u32,
+		transaction_version: version["transactionVersion"]
+			.as_u64()
+			.ok_or("Missing transaction version")? as u32,
+	})
+}
+
+/// Build the signed `system.remark` extrinsic of a canary.
+fn sign_remark(
+	signer: &sr25519::Pair,
+	canary: &Canary,
+	context: &SigningContext,
+) -> UncheckedExtrinsic {
+	let call = RuntimeCall::System(SystemCall::remark { remark: canary.remark.clone() });
+	let extra: SignedExtra = (
+		frame_system::CheckNonZeroSender::<Runtime>::new(),
+		frame_system::CheckSpecVersion::<Runtime>::new(),
+		frame_system::CheckTxVersion::<Runtime>::new(),
+		frame_system::CheckGenesis::<Runtime>::new(),
+		frame_system::CheckEra::<Runtime>::from(canary.era),
+		frame_system::CheckNonce::<Runtime>::from(canary.nonce),
+		frame_system::CheckWeight::<Runtime>::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+	);
+
+	let raw_payload = SignedPayload::from_raw(
+		call.clone(),
+		extra.clone(),
+		(
+			(),
+			context.spec_version,
+			context.transaction_version,
+			context.genesis_hash,
+			context.birth_hash,
+			(),
+			(),
+			(),
+		),
+	);
+	let signature = raw_payload.using_encoded(|e| signer.sign(e));
+
+	UncheckedExtrinsic::new_signed(
+		call,
+		AccountId::from(signer.public()).into(),
+		runtime::Signature::Sr25519(signature),
+		extra,
+	)
+}
+
+async fn header(client: &WsClient, hash: Hash) -> Result<Header> {
+	client
+		.request::<Option<Header>, _>("chain_getHeader", rpc_params![hash])
+		.await?
+		.ok_or_else(|| format!("Header of block {} not found", hash).into())
+}
+
+async fn block_hash(client: &WsClient, number: BlockNumber) -> Result<Hash> {
+	Ok(client
+		.request::<Option<Hash>, _>("chain_getBlockHash", rpc_params![number])
+		.await?
+		.ok_or_else(|| format!("Block #{} not found", number))?)
+}
+
+async fn header_at(client: &WsClient, number: BlockNumber) -> Result<Header> {
+	header(client, block_hash(client, number).await?).await
+}
+
+/// The Aura authority that authored the block with the given header.
+///
+/// The author is the authority owning the slot of the block, looked up in the authority set of
+/// its parent.
+async fn block_author(client: &WsClient, header: &Header) -> Result<Option<sr25519::Public>> {
+	let Some(slot) = header
+		.digest
+		.logs()
+		.iter()
+		.find_map(|item| item.pre_runtime_try_to::<Slot>(&AURA_ENGINE_ID))
+	else {
+		return Ok(None)
+	};
+
+	let encoded: Bytes = client
+		.request(
+			"state_call",
+			rpc_params!["AuraApi_authorities", Bytes(Vec::new()), header.parent_hash],
+		)
+		.await?;
+	let authorities = Vec::<sr25519::Public>::decode(&mut &encoded[..])?;
+	if authorities.is_empty() {
+		return Ok(None)
+	}
+
+	Ok(authorities.get((u64::from(slot) % authorities.len() as u64) as usize).cloned())
+}